@@ -0,0 +1,126 @@
+//! Benchmarks the power-of-two masked indexing in `LzCircularBuffer` against
+//! match-heavy input, i.e. long runs of `append_lz` calls with a small
+//! distance relative to the dictionary size. `modulo_baseline` re-implements
+//! just the pre-masking hot path (`% dict_size` indexing, lazily-grown `Vec`)
+//! so the two can be compared head to head in the same benchmark group; both
+//! arms flush the same bytes through an output sink at the same cadence so
+//! the comparison isolates the indexing strategy rather than I/O.
+
+use criterion::{black_box, criterion_group, criterion_main, BenchmarkId, Criterion};
+use lzma_rs::decode::lzbuffer::{LzBuffer, LzCircularBuffer};
+
+// A power-of-two dictionary size, so `LzCircularBuffer` takes its masked
+// fast path (masking is only used when `dict_size` is already a power of
+// two; anything else falls back to `% dict_size`, the same arithmetic
+// `modulo_baseline` uses below) and the two arms actually compare different
+// indexing strategies.
+const DICT_SIZE: usize = 1 << 16;
+const MEMLIMIT: usize = usize::MAX;
+const ITERS: usize = 100_000;
+
+mod modulo_baseline {
+    //! Minimal stand-in for `LzCircularBuffer` as it was before the
+    //! power-of-two masked indexing change, kept here only so the benchmark
+    //! below has something pre-masking to compare against. Flushes the whole
+    //! window to `out` on every lap, matching `LzCircularBuffer`'s per-lap
+    //! full-window `write_vectored`, and only the bytes before the cursor on
+    //! `finish`, matching `LzCircularBuffer::finish`.
+
+    pub struct CircularBuffer {
+        out: Vec<u8>,
+        buf: Vec<u8>,
+        dict_size: usize,
+        cursor: usize,
+        len: usize,
+    }
+
+    impl CircularBuffer {
+        pub fn new(dict_size: usize) -> Self {
+            Self {
+                out: Vec::new(),
+                buf: Vec::new(),
+                dict_size,
+                cursor: 0,
+                len: 0,
+            }
+        }
+
+        fn get(&self, index: usize) -> u8 {
+            *self.buf.get(index).unwrap_or(&0)
+        }
+
+        fn set(&mut self, index: usize, value: u8) {
+            if self.buf.len() <= index {
+                self.buf.resize(index + 1, 0);
+            }
+            self.buf[index] = value;
+        }
+
+        fn flush(&mut self) {
+            self.out.extend_from_slice(&self.buf);
+        }
+
+        pub fn append_literal(&mut self, lit: u8) {
+            self.set(self.cursor, lit);
+            self.cursor += 1;
+            self.len += 1;
+            if self.cursor == self.dict_size {
+                self.flush();
+                self.cursor = 0;
+            }
+        }
+
+        pub fn append_lz(&mut self, len: usize, dist: usize) {
+            let mut offset = (self.dict_size + self.cursor - dist) % self.dict_size;
+            for _ in 0..len {
+                let x = self.get(offset);
+                self.append_literal(x);
+                offset += 1;
+                if offset == self.dict_size {
+                    offset = 0;
+                }
+            }
+        }
+
+        pub fn finish(mut self) -> Vec<u8> {
+            let cursor = self.cursor;
+            self.out.extend_from_slice(&self.buf[..cursor]);
+            self.out
+        }
+    }
+}
+
+fn append_lz_match_heavy(c: &mut Criterion) {
+    let mut group = c.benchmark_group("append_lz match-heavy");
+
+    group.bench_function(BenchmarkId::new("masked", DICT_SIZE), |b| {
+        b.iter(|| {
+            let mut out = Vec::new();
+            let mut dict = Vec::new();
+            let mut buf = LzCircularBuffer::from_stream(&mut out, DICT_SIZE, MEMLIMIT, &mut dict);
+
+            buf.append_literal(0xAB).unwrap();
+            for _ in 0..ITERS {
+                buf.append_lz(black_box(8), black_box(1)).unwrap();
+            }
+            buf.finish().unwrap();
+        });
+    });
+
+    group.bench_function(BenchmarkId::new("modulo_baseline", DICT_SIZE), |b| {
+        b.iter(|| {
+            let mut buf = modulo_baseline::CircularBuffer::new(DICT_SIZE);
+
+            buf.append_literal(0xAB);
+            for _ in 0..ITERS {
+                buf.append_lz(black_box(8), black_box(1));
+            }
+            buf.finish();
+        });
+    });
+
+    group.finish();
+}
+
+criterion_group!(benches, append_lz_match_heavy);
+criterion_main!(benches);
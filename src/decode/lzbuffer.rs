@@ -1,5 +1,10 @@
 use crate::error;
-use std::io;
+use crate::io;
+
+#[cfg(not(feature = "std"))]
+use alloc::{format, vec::Vec};
+#[cfg(all(not(feature = "std"), test))]
+use alloc::{string::ToString, vec};
 
 pub trait LzBuffer<'a, W>
 where
@@ -9,6 +14,10 @@ where
     fn from_stream(stream: W, dict_size: usize, memlimit: usize, buf: &'a mut Vec<u8>) -> Self;
     // Retrieve the length of the buffer
     fn len(&self) -> usize;
+    // Retrieve whether the buffer is empty
+    fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
     // Retrieve the last byte or return a default
     fn last_or(&self, lit: u8) -> u8;
     // Retrieve the n-th last byte
@@ -39,6 +48,9 @@ where
     len: usize,           // Total number of bytes sent through the buffer
 }
 
+// `append_bytes`/`reset` are consumed by the LZMA2 chunk decoder in
+// `crate::decode::lzma`, which isn't part of this snapshot.
+#[allow(dead_code)]
 impl<'a, W> LzAccumBuffer<'a, W>
 where
     W: io::Write,
@@ -125,11 +137,9 @@ where
             )));
         }
 
-        let mut offset = buf_len - dist;
-        for _ in 0..len {
+        for offset in (buf_len - dist)..(buf_len - dist + len) {
             let x = self.buf[offset];
             self.buf.push(x);
-            offset += 1;
         }
         self.len += len;
         Ok(())
@@ -165,11 +175,16 @@ where
     W: io::Write,
 {
     stream: W,            // Output sink
-    buf: &'a mut Vec<u8>, // Circular buffer
-    dict_size: usize,     // Length of the buffer
-    memlimit: usize,      // Buffer memory limit
-    cursor: usize,        // Current position
-    len: usize,           // Total number of bytes sent through the buffer
+    buf: &'a mut Vec<u8>, // Circular buffer, grown lazily up to `dict_size` bytes
+    dict_size: usize,     // Length of the addressable dictionary window
+    // `Some(dict_size - 1)` when `dict_size` is a power of two, letting
+    // `rewind`/`advance` use a branch-free masked index instead of `%
+    // dict_size`. `None` otherwise, since masking to the next power of two
+    // would let the buffer grow past `dict_size` (see `rewind`/`advance`).
+    mask: Option<usize>,
+    memlimit: usize, // Buffer memory limit
+    cursor: usize,   // Current position
+    len: usize,      // Total number of bytes sent through the buffer
 }
 
 impl<'a, W> LzCircularBuffer<'a, W>
@@ -196,6 +211,62 @@ where
         self.buf[index] = value;
         Ok(())
     }
+
+    // Position `dist` bytes behind the cursor within the circular window.
+    // The masked path is branch-free but only preserves `% dict_size`
+    // semantics when `dict_size` is already a power of two; for any other
+    // size it falls back to an explicit wrap check so the buffer never
+    // needs to grow past `dict_size` bytes.
+    fn rewind(&self, dist: usize) -> usize {
+        match self.mask {
+            Some(mask) => self.cursor.wrapping_sub(dist) & mask,
+            None if dist <= self.cursor => self.cursor - dist,
+            None => self.cursor + self.dict_size - dist,
+        }
+    }
+
+    // Advance a position by one slot, wrapping at the end of the window.
+    fn advance(&self, index: usize) -> usize {
+        match self.mask {
+            Some(mask) => index.wrapping_add(1) & mask,
+            None if index + 1 == self.dict_size => 0,
+            None => index + 1,
+        }
+    }
+
+    // Flush the live window to the output sink. The window is logically the
+    // tail segment `buf[cursor..]` followed by the head segment
+    // `buf[0..cursor]` (`append_literal` calls this right after `cursor` has
+    // wrapped to 0, so the head segment is empty and the tail segment is the
+    // whole window; the split lets a flush at any other cursor position emit
+    // the bytes in order without rearranging them first). `write_vectored` is
+    // allowed to write only part of the given
+    // buffers (e.g. a sink that doesn't override it forwards to a single
+    // short `write`), so loop until both segments are fully written --
+    // `std::io::Write::write_all_vectored` would do this for us, but it's
+    // still nightly-only.
+    fn flush_vectored(&mut self) -> io::Result<()> {
+        let (mut head, mut tail) = self.buf.split_at(self.cursor);
+        // `split_at` returns (buf[0..cursor], buf[cursor..]) = (head, tail);
+        // the window is written tail-then-head (see above).
+        while !tail.is_empty() || !head.is_empty() {
+            let bufs = [io::IoSlice::new(tail), io::IoSlice::new(head)];
+            let written = self.stream.write_vectored(&bufs)?;
+            if written == 0 {
+                return Err(io::Error::new(
+                    io::ErrorKind::WriteZero,
+                    "failed to write whole buffer",
+                ));
+            }
+            if written < tail.len() {
+                tail = &tail[written..];
+            } else {
+                head = &head[written - tail.len()..];
+                tail = &[];
+            }
+        }
+        Ok(())
+    }
 }
 
 impl<'a, W> LzBuffer<'a, W> for LzCircularBuffer<'a, W>
@@ -208,6 +279,7 @@ where
             stream,
             buf,
             dict_size,
+            mask: dict_size.is_power_of_two().then(|| dict_size - 1),
             memlimit,
             cursor: 0,
             len: 0,
@@ -223,7 +295,7 @@ where
         if self.len == 0 {
             lit
         } else {
-            self.get((self.dict_size + self.cursor - 1) % self.dict_size)
+            self.get(self.rewind(1))
         }
     }
 
@@ -242,20 +314,19 @@ where
             )));
         }
 
-        let offset = (self.dict_size + self.cursor - dist) % self.dict_size;
-        Ok(self.get(offset))
+        Ok(self.get(self.rewind(dist)))
     }
 
     // Append a literal
     fn append_literal(&mut self, lit: u8) -> error::Result<()> {
         self.set(self.cursor, lit)?;
-        self.cursor += 1;
+        self.cursor = self.advance(self.cursor);
         self.len += 1;
 
-        // Flush the circular buffer to the output
-        if self.cursor == self.dict_size {
-            self.stream.write_all(self.buf.as_slice())?;
-            self.cursor = 0;
+        // Flush the circular buffer to the output once a full lap of the
+        // window has been written.
+        if self.cursor == 0 {
+            self.flush_vectored()?;
         }
 
         Ok(())
@@ -277,14 +348,11 @@ where
             )));
         }
 
-        let mut offset = (self.dict_size + self.cursor - dist) % self.dict_size;
+        let mut offset = self.rewind(dist);
         for _ in 0..len {
             let x = self.get(offset);
             self.append_literal(x)?;
-            offset += 1;
-            if offset == self.dict_size {
-                offset = 0
-            }
+            offset = self.advance(offset);
         }
         Ok(())
     }
@@ -313,3 +381,193 @@ where
         self.stream
     }
 }
+
+#[cfg(test)]
+mod circular_buffer_test {
+    use super::*;
+
+    #[test]
+    fn round_trip_non_power_of_two_dict_size() {
+        let mut out = Vec::new();
+        let mut dict = Vec::new();
+        // `dict_size` of 3 exercises the modulo fallback (masking is only
+        // used when `dict_size` is already a power of two).
+        let mut buf = LzCircularBuffer::from_stream(&mut out, 3, 3, &mut dict);
+
+        buf.append_literal(1).unwrap();
+        buf.append_literal(2).unwrap();
+        buf.append_literal(3).unwrap(); // wraps, flushes [1, 2, 3]
+        buf.append_lz(3, 3).unwrap(); // re-copies [1, 2, 3], wraps again
+
+        buf.finish().unwrap();
+        assert_eq!(out, vec![1, 2, 3, 1, 2, 3]);
+    }
+
+    #[test]
+    fn match_distance_read_back_across_flush_and_wrap() {
+        let mut out = Vec::new();
+        let mut dict = Vec::new();
+        let mut buf = LzCircularBuffer::from_stream(&mut out, 4, 4, &mut dict);
+
+        buf.append_literal(1).unwrap();
+        buf.append_literal(2).unwrap();
+        buf.append_literal(3).unwrap();
+        buf.append_literal(4).unwrap(); // wraps, flushes [1, 2, 3, 4]
+        buf.append_literal(5).unwrap();
+        buf.append_literal(6).unwrap();
+        // Distance 4 reaches back across the flush to the untouched `3, 4`
+        // from the previous lap, even though this lap already overwrote the
+        // first two slots with `5, 6`.
+        buf.append_lz(2, 4).unwrap();
+
+        buf.finish().unwrap();
+        assert_eq!(out, vec![1, 2, 3, 4, 5, 6, 3, 4]);
+    }
+
+    #[test]
+    fn rle_dist_one_crosses_flush_boundary() {
+        let mut out = Vec::new();
+        let mut dict = Vec::new();
+        let mut buf = LzCircularBuffer::from_stream(&mut out, 3, 3, &mut dict);
+
+        buf.append_literal(9).unwrap();
+        // len (5) spans two flushes of the 3-byte window; each copied byte
+        // must be visible to the next within the same call.
+        buf.append_lz(5, 1).unwrap();
+
+        buf.finish().unwrap();
+        assert_eq!(out, vec![9; 6]);
+    }
+}
+
+/// A zero-copy LZ buffer that decodes directly into a caller-provided
+/// `&'a mut [u8]`, serving as its own dictionary.
+///
+/// Unlike [`LzAccumBuffer`] and [`LzCircularBuffer`] there is no separate
+/// output sink to flush to and nothing to allocate: the slice is both the
+/// dictionary and the final destination. Because of that it does not
+/// implement [`LzBuffer`] (whose `finish` hands back the stream `W`) but
+/// exposes the same operations directly, returning the number of bytes
+/// written from `finish` instead. Useful when the decompressed size is
+/// known ahead of time and the caller wants allocation-free decompression
+/// into a preallocated frame.
+#[derive(Debug)]
+pub struct LzSliceBuffer<'a> {
+    buf: &'a mut [u8], // Output sink and dictionary
+    cursor: usize,     // Current position, also the number of bytes written
+}
+
+impl<'a> LzSliceBuffer<'a> {
+    /// Create a new buffer that decodes into `buf`.
+    pub fn from_mut_slice(buf: &'a mut [u8]) -> Self {
+        Self { buf, cursor: 0 }
+    }
+
+    // Retrieve the length of the buffer
+    pub fn len(&self) -> usize {
+        self.cursor
+    }
+
+    // Retrieve whether the buffer is empty
+    pub fn is_empty(&self) -> bool {
+        self.cursor == 0
+    }
+
+    // Retrieve the last byte or return a default
+    pub fn last_or(&self, lit: u8) -> u8 {
+        if self.cursor == 0 {
+            lit
+        } else {
+            self.buf[self.cursor - 1]
+        }
+    }
+
+    // Retrieve the n-th last byte
+    pub fn last_n(&self, dist: usize) -> error::Result<u8> {
+        if dist > self.cursor {
+            return Err(error::Error::LzmaError(format!(
+                "Match distance {} is beyond output size {}",
+                dist, self.cursor
+            )));
+        }
+
+        Ok(self.buf[self.cursor - dist])
+    }
+
+    // Append a literal
+    pub fn append_literal(&mut self, lit: u8) -> error::Result<()> {
+        if self.cursor >= self.buf.len() {
+            return Err(error::Error::LzmaError(format!(
+                "output slice of size {} is full",
+                self.buf.len()
+            )));
+        }
+
+        self.buf[self.cursor] = lit;
+        self.cursor += 1;
+        Ok(())
+    }
+
+    // Fetch an LZ sequence (length, distance) from inside the buffer
+    pub fn append_lz(&mut self, len: usize, dist: usize) -> error::Result<()> {
+        lzma_debug!("LZ {{ len: {}, dist: {} }}", len, dist);
+        if dist > self.cursor {
+            return Err(error::Error::LzmaError(format!(
+                "LZ distance {} is beyond output size {}",
+                dist, self.cursor
+            )));
+        }
+
+        for offset in (self.cursor - dist)..(self.cursor - dist + len) {
+            let x = self.buf[offset];
+            self.append_literal(x)?;
+        }
+        Ok(())
+    }
+
+    /// Consumes this buffer, returning the number of bytes written.
+    pub fn finish(self) -> usize {
+        self.cursor
+    }
+}
+
+#[cfg(test)]
+mod slice_buffer_test {
+    use super::*;
+
+    #[test]
+    fn append_lz_overlapping_match() {
+        let mut out = [0u8; 8];
+        let mut buf = LzSliceBuffer::from_mut_slice(&mut out);
+
+        buf.append_literal(1).unwrap();
+        // dist < len: each byte copied must be visible to the next copy.
+        buf.append_lz(7, 1).unwrap();
+
+        assert_eq!(buf.finish(), 8);
+        assert_eq!(out, [1, 1, 1, 1, 1, 1, 1, 1]);
+    }
+
+    #[test]
+    fn append_literal_errors_when_slice_is_full() {
+        let mut out = [0u8; 2];
+        let mut buf = LzSliceBuffer::from_mut_slice(&mut out);
+
+        buf.append_literal(1).unwrap();
+        buf.append_literal(2).unwrap();
+
+        let err = buf.append_literal(3).unwrap_err();
+        assert!(err.to_string().contains("output slice of size 2 is full"));
+    }
+
+    #[test]
+    fn finish_returns_bytes_written() {
+        let mut out = [0u8; 4];
+        let mut buf = LzSliceBuffer::from_mut_slice(&mut out);
+
+        buf.append_literal(1).unwrap();
+        buf.append_literal(2).unwrap();
+
+        assert_eq!(buf.finish(), 2);
+    }
+}
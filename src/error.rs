@@ -0,0 +1,31 @@
+use core::fmt;
+
+#[cfg(not(feature = "std"))]
+use alloc::string::String;
+
+/// Error type used by this crate.
+#[derive(Debug)]
+pub enum Error {
+    IoError(crate::io::Error),
+    LzmaError(String),
+}
+
+impl fmt::Display for Error {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Error::IoError(e) => write!(f, "io error: {}", e),
+            Error::LzmaError(e) => write!(f, "lzma error: {}", e),
+        }
+    }
+}
+
+impl From<crate::io::Error> for Error {
+    fn from(e: crate::io::Error) -> Self {
+        Error::IoError(e)
+    }
+}
+
+#[cfg(feature = "std")]
+impl std::error::Error for Error {}
+
+pub type Result<T> = core::result::Result<T, Error>;
@@ -0,0 +1,130 @@
+//! A small internal I/O abstraction that lets the decode path compile under
+//! both `std` and `no_std` (+ `alloc`) targets.
+//!
+//! With the default `std` feature enabled, [`Write`], [`Result`], [`Error`]
+//! and [`IoSlice`] are plain re-exports of their `std::io` counterparts, so
+//! any type that already implements `std::io::Write` (files, sockets,
+//! `Vec<u8>`, ...) implements `crate::io::Write` for free and the public API
+//! is byte-for-byte unchanged. With `--no-default-features`, a minimal
+//! `core` + `alloc` fallback below provides just enough of the same surface
+//! for [`crate::decode::lzbuffer`] to compile on embedded/bare-metal
+//! targets.
+
+#[cfg(feature = "std")]
+pub use std::io::{Error, ErrorKind, IoSlice, Result, Write};
+
+#[cfg(not(feature = "std"))]
+mod no_std_io {
+    use alloc::vec::Vec;
+    use core::fmt;
+
+    /// Stand-in for [`std::io::ErrorKind`], covering only `WriteZero` (the
+    /// one variant this crate itself produces) and a catch-all `Other`; there
+    /// is no OS to report error codes for under `no_std`.
+    #[derive(Debug, Clone, Copy, PartialEq, Eq)]
+    pub enum ErrorKind {
+        WriteZero,
+        Other,
+    }
+
+    /// Stand-in for [`std::io::Error`] that does not depend on OS error
+    /// codes, so it can be constructed in `no_std` contexts.
+    #[derive(Debug)]
+    pub struct Error {
+        kind: ErrorKind,
+    }
+
+    impl Error {
+        // `msg` is accepted (and dropped) only to keep this constructor's
+        // signature call-compatible with `std::io::Error::new`.
+        pub fn new(kind: ErrorKind, _msg: &'static str) -> Self {
+            Self { kind }
+        }
+
+        pub fn kind(&self) -> ErrorKind {
+            self.kind
+        }
+    }
+
+    impl fmt::Display for Error {
+        fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+            match self.kind {
+                ErrorKind::WriteZero => write!(f, "failed to write whole buffer"),
+                ErrorKind::Other => write!(f, "I/O error"),
+            }
+        }
+    }
+
+    pub type Result<T> = core::result::Result<T, Error>;
+
+    /// Stand-in for [`std::io::IoSlice`], just enough for
+    /// [`Write::write_vectored`].
+    #[derive(Debug, Copy, Clone)]
+    pub struct IoSlice<'a>(&'a [u8]);
+
+    impl<'a> IoSlice<'a> {
+        pub fn new(buf: &'a [u8]) -> Self {
+            Self(buf)
+        }
+    }
+
+    impl<'a> core::ops::Deref for IoSlice<'a> {
+        type Target = [u8];
+
+        fn deref(&self) -> &[u8] {
+            self.0
+        }
+    }
+
+    /// Stand-in for [`std::io::Write`], implemented here for the `alloc`
+    /// sinks this crate actually writes into.
+    pub trait Write {
+        fn write_all(&mut self, buf: &[u8]) -> Result<()>;
+        fn flush(&mut self) -> Result<()>;
+
+        fn is_write_vectored(&self) -> bool {
+            false
+        }
+
+        fn write_vectored(&mut self, bufs: &[IoSlice<'_>]) -> Result<usize> {
+            let mut written = 0;
+            for buf in bufs {
+                self.write_all(buf)?;
+                written += buf.len();
+            }
+            Ok(written)
+        }
+    }
+
+    impl Write for Vec<u8> {
+        fn write_all(&mut self, buf: &[u8]) -> Result<()> {
+            self.extend_from_slice(buf);
+            Ok(())
+        }
+
+        fn flush(&mut self) -> Result<()> {
+            Ok(())
+        }
+    }
+
+    impl<W: Write + ?Sized> Write for &mut W {
+        fn write_all(&mut self, buf: &[u8]) -> Result<()> {
+            (**self).write_all(buf)
+        }
+
+        fn flush(&mut self) -> Result<()> {
+            (**self).flush()
+        }
+
+        fn is_write_vectored(&self) -> bool {
+            (**self).is_write_vectored()
+        }
+
+        fn write_vectored(&mut self, bufs: &[IoSlice<'_>]) -> Result<usize> {
+            (**self).write_vectored(bufs)
+        }
+    }
+}
+
+#[cfg(not(feature = "std"))]
+pub use no_std_io::{Error, ErrorKind, IoSlice, Result, Write};
@@ -0,0 +1,21 @@
+#![cfg_attr(not(feature = "std"), no_std)]
+
+#[cfg(not(feature = "std"))]
+extern crate alloc;
+
+// Lightweight logging hooks used throughout the decode path. Defined here
+// (rather than pulling in a logging crate) so they stay usable in `no_std`
+// builds; textual macro scoping makes them visible in every module declared
+// below without an explicit `use`.
+macro_rules! lzma_info {
+    ($($arg:tt)*) => {};
+}
+
+macro_rules! lzma_debug {
+    ($($arg:tt)*) => {};
+}
+
+pub mod decode;
+pub mod error;
+pub mod io;
+pub mod util;
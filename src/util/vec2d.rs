@@ -1,4 +1,6 @@
-use std::ops::{Index, IndexMut};
+#[cfg(not(feature = "std"))]
+use alloc::{boxed::Box, vec};
+use core::ops::{Index, IndexMut};
 
 /// A 2 dimensional matrix in row-major order backed by a contiguous `Vec`
 #[derive(Debug)]